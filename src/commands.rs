@@ -0,0 +1,280 @@
+use std::time::Duration;
+
+use crate::database::{MemoryDatabase, Value, ValueConvert};
+use crate::resp::Resp;
+
+/// Executes a decoded RESP request against the database and returns the reply.
+///
+/// A request is expected to be an array of bulk strings, as sent by a Redis
+/// client. Unknown commands and arity errors surface as `-ERR ...` replies
+/// rather than panics, mirroring how a real server answers a misbehaving peer.
+pub fn dispatch(db: &mut MemoryDatabase, request: Resp) -> Resp {
+    let args = match into_args(request) {
+        Some(args) if !args.is_empty() => args,
+        _ => return error("ERR expected a non-empty array of bulk strings"),
+    };
+
+    let name = String::from_utf8_lossy(&args[0]).to_uppercase();
+
+    match name.as_str() {
+        "SET" => set(db, &args),
+        "GET" => get(db, &args),
+        "DEL" => del(db, &args),
+        "EXISTS" => exists(db, &args),
+        "TTL" => ttl(db, &args),
+        "PX" => set_expiry(db, &args, "px"),
+        "EXPIRE" => set_expiry(db, &args, "expire"),
+        other => error(&format!("ERR unknown command '{}'", other)),
+    }
+}
+
+fn set(db: &mut MemoryDatabase, args: &[Vec<u8>]) -> Resp {
+    if args.len() != 3 && args.len() != 5 {
+        return wrong_arity("set");
+    }
+
+    let key = String::from_utf8_lossy(&args[1]).into_owned();
+    let value = coerce(&args[2]);
+
+    // Optional `PX <ms>` / `EX <sec>` modifier routes into the TTL subsystem.
+    let ttl = if args.len() == 5 {
+        match parse_expiry_modifier(&args[3], &args[4]) {
+            Ok(duration) => Some(duration),
+            Err(reply) => return reply,
+        }
+    } else {
+        None
+    };
+
+    let result = match ttl {
+        Some(duration) => db.set_with_ttl(key, value, duration),
+        None => db.set(key, value),
+    };
+
+    match result {
+        Ok(()) => Resp::SimpleString(String::from("OK")),
+        Err(_) => error("ERR value exceeds the maximum allowed size"),
+    }
+}
+
+fn get(db: &mut MemoryDatabase, args: &[Vec<u8>]) -> Resp {
+    if args.len() != 2 {
+        return wrong_arity("get");
+    }
+
+    let key = String::from_utf8_lossy(&args[1]).into_owned();
+    match db.get(&key) {
+        Some(value) => Resp::BulkString(Some(value_to_bytes(value))),
+        None => Resp::BulkString(None),
+    }
+}
+
+fn del(db: &mut MemoryDatabase, args: &[Vec<u8>]) -> Resp {
+    if args.len() < 2 {
+        return wrong_arity("del");
+    }
+
+    let mut removed = 0;
+    for key in &args[1..] {
+        let key = String::from_utf8_lossy(key);
+        if db.remove(&key).is_ok() {
+            removed += 1;
+        }
+    }
+
+    Resp::Integer(removed)
+}
+
+fn exists(db: &mut MemoryDatabase, args: &[Vec<u8>]) -> Resp {
+    if args.len() < 2 {
+        return wrong_arity("exists");
+    }
+
+    let mut found = 0;
+    for key in &args[1..] {
+        let key = String::from_utf8_lossy(key);
+        if db.get(&key).is_some() {
+            found += 1;
+        }
+    }
+
+    Resp::Integer(found)
+}
+
+fn ttl(db: &mut MemoryDatabase, args: &[Vec<u8>]) -> Resp {
+    if args.len() != 2 {
+        return wrong_arity("ttl");
+    }
+
+    let key = String::from_utf8_lossy(&args[1]).into_owned();
+    match db.ttl(&key) {
+        None => Resp::Integer(-2),
+        Some(None) => Resp::Integer(-1),
+        // Round the remaining time up so a live key with under a second left
+        // reports `1` rather than `0`.
+        Some(Some(duration)) => Resp::Integer(duration.as_nanos().div_ceil(1_000_000_000) as i64),
+    }
+}
+
+fn set_expiry(db: &mut MemoryDatabase, args: &[Vec<u8>], command: &str) -> Resp {
+    if args.len() != 3 {
+        return wrong_arity(command);
+    }
+
+    let key = String::from_utf8_lossy(&args[1]).into_owned();
+    let duration = match parse_duration(&args[2], command == "px") {
+        Ok(duration) => duration,
+        Err(reply) => return reply,
+    };
+
+    Resp::Integer(if db.set_expiry(&key, duration) { 1 } else { 0 })
+}
+
+/// Coerces raw request bytes into a typed [`Value`].
+///
+/// Stored as an integer or float when the payload parses cleanly, otherwise as
+/// a string.
+fn coerce(bytes: &[u8]) -> Value {
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(_) => return Value::String(String::from_utf8_lossy(bytes).into_owned()),
+    };
+
+    if let Ok(int) = text.parse::<i32>() {
+        return Value::Integer(int);
+    }
+
+    if let Ok(float) = text.parse::<f64>() {
+        return Value::Float(float);
+    }
+
+    Value::String(text.to_string())
+}
+
+/// Renders a stored [`Value`] as the bytes of a bulk-string reply.
+fn value_to_bytes(value: &Value) -> Vec<u8> {
+    if let Ok(s) = <Value as ValueConvert<String>>::to(value) {
+        return s.clone().into_bytes();
+    }
+    if let Ok(n) = <Value as ValueConvert<i32>>::to(value) {
+        return n.to_string().into_bytes();
+    }
+    if let Ok(f) = <Value as ValueConvert<f64>>::to(value) {
+        return f.to_string().into_bytes();
+    }
+    if let Ok(b) = <Value as ValueConvert<bool>>::to(value) {
+        return b.to_string().into_bytes();
+    }
+    Vec::new()
+}
+
+/// Parses a `PX <ms>` / `EX <sec>` pair into a duration.
+fn parse_expiry_modifier(modifier: &[u8], amount: &[u8]) -> Result<Duration, Resp> {
+    match String::from_utf8_lossy(modifier).to_uppercase().as_str() {
+        "PX" => parse_duration(amount, true),
+        "EX" => parse_duration(amount, false),
+        other => Err(error(&format!("ERR unsupported SET modifier '{}'", other))),
+    }
+}
+
+fn parse_duration(amount: &[u8], millis: bool) -> Result<Duration, Resp> {
+    let value: u64 = String::from_utf8_lossy(amount)
+        .parse()
+        .map_err(|_| error("ERR value is not an integer or out of range"))?;
+
+    Ok(if millis {
+        Duration::from_millis(value)
+    } else {
+        Duration::from_secs(value)
+    })
+}
+
+/// Extracts a request's arguments from an array of bulk strings.
+fn into_args(request: Resp) -> Option<Vec<Vec<u8>>> {
+    match request {
+        Resp::Array(Some(items)) => items
+            .into_iter()
+            .map(|item| match item {
+                Resp::BulkString(Some(bytes)) => Some(bytes),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+fn error(message: &str) -> Resp {
+    Resp::Error(message.to_string())
+}
+
+fn wrong_arity(command: &str) -> Resp {
+    error(&format!(
+        "ERR wrong number of arguments for '{}' command",
+        command
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::resp::{encode, Parser};
+
+    fn dispatch_raw(db: &mut MemoryDatabase, input: &[u8]) -> Vec<u8> {
+        let request = Parser::new(input).parse().unwrap();
+        encode(&dispatch(db, request))
+    }
+
+    #[test]
+    fn test_set_then_get_round_trip() {
+        let mut db = MemoryDatabase::new();
+
+        let reply = dispatch_raw(&mut db, b"*3\r\n$3\r\nSET\r\n$5\r\nmykey\r\n$5\r\nhello\r\n");
+        assert_eq!(reply, b"+OK\r\n");
+
+        let reply = dispatch_raw(&mut db, b"*2\r\n$3\r\nGET\r\n$5\r\nmykey\r\n");
+        assert_eq!(reply, b"$5\r\nhello\r\n");
+    }
+
+    #[test]
+    fn test_get_missing_is_null() {
+        let mut db = MemoryDatabase::new();
+
+        let reply = dispatch_raw(&mut db, b"*2\r\n$3\r\nGET\r\n$3\r\nnil\r\n");
+        assert_eq!(reply, b"$-1\r\n");
+    }
+
+    #[test]
+    fn test_del_and_exists_counts() {
+        let mut db = MemoryDatabase::new();
+        db.set(String::from("a"), Value::Integer(1)).unwrap();
+
+        let reply = dispatch_raw(&mut db, b"*2\r\n$6\r\nEXISTS\r\n$1\r\na\r\n");
+        assert_eq!(reply, b":1\r\n");
+
+        let reply = dispatch_raw(&mut db, b"*2\r\n$3\r\nDEL\r\n$1\r\na\r\n");
+        assert_eq!(reply, b":1\r\n");
+    }
+
+    #[test]
+    fn test_unknown_command_is_error() {
+        let mut db = MemoryDatabase::new();
+
+        let reply = dispatch_raw(&mut db, b"*1\r\n$4\r\nPING\r\n");
+        assert_eq!(reply, b"-ERR unknown command 'PING'\r\n");
+    }
+
+    #[test]
+    fn test_set_with_px_expires() {
+        let mut db = MemoryDatabase::new();
+
+        let reply = dispatch_raw(
+            &mut db,
+            b"*5\r\n$3\r\nSET\r\n$1\r\nk\r\n$1\r\nv\r\n$2\r\nPX\r\n$1\r\n0\r\n",
+        );
+        assert_eq!(reply, b"+OK\r\n");
+
+        // The zero-millisecond deadline is already past, so the key is gone.
+        let reply = dispatch_raw(&mut db, b"*2\r\n$3\r\nGET\r\n$1\r\nk\r\n");
+        assert_eq!(reply, b"$-1\r\n");
+    }
+}
@@ -1,6 +1,10 @@
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Value {
     Integer(i32),
     String(String),
@@ -8,6 +12,7 @@ pub enum Value {
     Boolean(bool),
 }
 
+#[allow(clippy::result_unit_err)]
 pub trait ValueConvert<T> {
     fn to(&self) -> Result<&T, ()>;
 }
@@ -15,8 +20,8 @@ pub trait ValueConvert<T> {
 impl ValueConvert<String> for Value {
     fn to(&self) -> Result<&String, ()> {
         match self {
-            Value::String(value) => return Ok(value),
-            _ => return Err(()),
+            Value::String(value) => Ok(value),
+            _ => Err(()),
         }
     }
 }
@@ -24,8 +29,8 @@ impl ValueConvert<String> for Value {
 impl ValueConvert<i32> for Value {
     fn to(&self) -> Result<&i32, ()> {
         match self {
-            Value::Integer(value) => return Ok(value),
-            _ => return Err(()),
+            Value::Integer(value) => Ok(value),
+            _ => Err(()),
         }
     }
 }
@@ -33,8 +38,8 @@ impl ValueConvert<i32> for Value {
 impl ValueConvert<f64> for Value {
     fn to(&self) -> Result<&f64, ()> {
         match self {
-            Value::Float(value) => return Ok(value),
-            _ => return Err(()),
+            Value::Float(value) => Ok(value),
+            _ => Err(()),
         }
     }
 }
@@ -42,8 +47,8 @@ impl ValueConvert<f64> for Value {
 impl ValueConvert<bool> for Value {
     fn to(&self) -> Result<&bool, ()> {
         match self {
-            Value::Boolean(value) => return Ok(value),
-            _ => return Err(()),
+            Value::Boolean(value) => Ok(value),
+            _ => Err(()),
         }
     }
 }
@@ -54,8 +59,50 @@ pub enum DatabaseError {
     MaxSizeExceeded,
 }
 
+/// A single stored value together with its optional expiry deadline and the
+/// bookkeeping used by the LRU eviction policy.
+///
+/// The deadline mirrors the `last_used`/`elapsed()` recency pattern from the
+/// external cache: an entry whose `expires_at` lies in the past is treated as
+/// absent and reaped either lazily on `get` or during an `expire_pass`.
+/// `last_used`/`seq` track recency so the least-recently-used victim can be
+/// found in `O(log n)` via the `order` index on [`MemoryDatabase`].
+#[derive(Debug)]
+struct Entry {
+    value: Value,
+    expires_at: Option<Instant>,
+    last_used: Instant,
+    seq: u64,
+}
+
+/// Selects what happens when an insert would push the database past `max_size`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Reject the insert with [`DatabaseError::MaxSizeExceeded`]
+    RejectOnFull,
+    /// Evict least-recently-used entries until the new value fits
+    Lru,
+}
+
+impl Entry {
+    /// Returns `true` when the entry carries a deadline that has already passed.
+    fn is_expired(&self) -> bool {
+        match self.expires_at {
+            Some(deadline) => deadline <= Instant::now(),
+            None => false,
+        }
+    }
+}
+
 pub struct MemoryDatabase {
-    items: HashMap<String, Value>,
+    items: HashMap<String, Entry>,
+    /// Recency index mapping each entry's access counter to its key.
+    ///
+    /// Invariant: `order` and `items` always hold exactly the same key set, so
+    /// the smallest key in `order` is the least-recently-used victim.
+    order: BTreeMap<u64, String>,
+    access_counter: u64,
+    policy: EvictionPolicy,
     size: i32,
     max_size: i32,
 }
@@ -76,8 +123,23 @@ impl MemoryDatabase {
     /// assert_eq!(item, "test");
     /// ```
     pub fn new() -> Self {
+        Self::with_policy(EvictionPolicy::RejectOnFull)
+    }
+
+    /// Creates a new instance of MemoryDatabase with the given eviction policy
+    ///
+    /// # Examples
+    /// ```
+    /// use rscache::{MemoryDatabase, EvictionPolicy};
+    ///
+    /// let db = MemoryDatabase::with_policy(EvictionPolicy::Lru);
+    /// ```
+    pub fn with_policy(policy: EvictionPolicy) -> Self {
         Self {
             items: HashMap::new(),
+            order: BTreeMap::new(),
+            access_counter: 0,
+            policy,
             size: 0,
             max_size: 1024 * 1024,
         }
@@ -114,16 +176,121 @@ impl MemoryDatabase {
     /// assert_eq!(db.size(), 35);
     /// ```
     pub fn set(&mut self, key: String, value: Value) -> Result<(), DatabaseError> {
+        self.insert(key, value, None)
+    }
+
+    /// Sets a key-value pair that expires after `ttl` has elapsed
+    ///
+    /// The deadline is computed from the current instant; a later plain `set`
+    /// (or another `set_with_ttl`) of the same key replaces the entry and
+    /// therefore resets its expiry.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use rscache::{MemoryDatabase, Value};
+    ///
+    /// let mut db = MemoryDatabase::new();
+    /// db.set_with_ttl(String::from("session"), Value::String(String::from("token")), Duration::from_millis(50));
+    /// ```
+    pub fn set_with_ttl(
+        &mut self,
+        key: String,
+        value: Value,
+        ttl: Duration,
+    ) -> Result<(), DatabaseError> {
+        self.insert(key, value, Some(Instant::now() + ttl))
+    }
+
+    /// Inserts an entry, accounting for the space freed by any value it replaces
+    ///
+    /// Under [`EvictionPolicy::Lru`] least-recently-used entries are evicted
+    /// until the new value fits; under [`EvictionPolicy::RejectOnFull`] an
+    /// overflowing insert fails with [`DatabaseError::MaxSizeExceeded`].
+    fn insert(
+        &mut self,
+        key: String,
+        value: Value,
+        expires_at: Option<Instant>,
+    ) -> Result<(), DatabaseError> {
         let item_size = Self::calculate_value_size(key.as_ref(), &value);
+        let previous_size = self
+            .items
+            .get(&key)
+            .map(|entry| Self::calculate_value_size(key.as_ref(), &entry.value))
+            .unwrap_or(0);
+
+        // Decide whether the insert can succeed *before* removing anything, so
+        // a rejected overwrite never destroys the pre-existing value.
+        match self.policy {
+            EvictionPolicy::RejectOnFull => {
+                if self.size - previous_size + item_size > self.max_size {
+                    return Err(DatabaseError::MaxSizeExceeded);
+                }
+            }
+            EvictionPolicy::Lru => {
+                // A value larger than the whole budget can never fit, even after
+                // evicting everything else; reject it without touching data.
+                if item_size > self.max_size {
+                    return Err(DatabaseError::MaxSizeExceeded);
+                }
+            }
+        }
 
-        if self.size + item_size > self.max_size {
-            return Err(DatabaseError::MaxSizeExceeded);
+        // The insert is now guaranteed to succeed, so it is safe to drop any
+        // previous entry for this key and (for LRU) evict until the value fits.
+        self.unlink(&key);
+        while self.size + item_size > self.max_size {
+            let seq = self
+                .order
+                .keys()
+                .next()
+                .copied()
+                .expect("order and items share a key set, so a victim must exist");
+            let victim = self.order[&seq].clone();
+            self.unlink(&victim);
         }
 
-        self.items.insert(key, value);
+        let seq = self.next_seq();
+        self.order.insert(seq, key.clone());
+        self.items.insert(
+            key,
+            Entry {
+                value,
+                expires_at,
+                last_used: Instant::now(),
+                seq,
+            },
+        );
         self.size += item_size;
 
-        return Ok(());
+        Ok(())
+    }
+
+    /// Removes an entry from both `items` and the `order` index, reclaiming its
+    /// space. Preserves the invariant that both structures share a key set.
+    fn unlink(&mut self, key: &str) {
+        if let Some(entry) = self.items.remove(key) {
+            self.order.remove(&entry.seq);
+            self.size -= Self::calculate_value_size(key, &entry.value);
+        }
+    }
+
+    /// Marks `key` as most-recently-used, moving it to the tail of the ordering
+    fn touch(&mut self, key: &str) {
+        let seq = self.next_seq();
+        if let Some(entry) = self.items.get_mut(key) {
+            self.order.remove(&entry.seq);
+            entry.seq = seq;
+            entry.last_used = Instant::now();
+            self.order.insert(seq, key.to_string());
+        }
+    }
+
+    /// Returns the next monotonically increasing access counter
+    fn next_seq(&mut self) -> u64 {
+        self.access_counter += 1;
+        self.access_counter
     }
 
     /// Gets a value from the database
@@ -140,14 +307,50 @@ impl MemoryDatabase {
     ///
     /// assert_eq!(value, "test");
     /// ```
-    pub fn get(&self, key: &str) -> Option<&Value> {
-        let item = self.items.get(key);
+    pub fn get(&mut self, key: &str) -> Option<&Value> {
+        // Lazy expiry: an entry past its deadline is reaped on access so its
+        // space is reclaimed and `size` accounting stays correct.
+        if let Some(entry) = self.items.get(key) {
+            if entry.is_expired() {
+                self.unlink(key);
+                return None;
+            }
+        }
 
-        if let Some(item) = item {
-            return Some(&item);
+        if self.items.contains_key(key) {
+            self.touch(key);
         }
 
-        None
+        self.items.get(key).map(|entry| &entry.value)
+    }
+
+    /// Purges every entry whose deadline has passed
+    ///
+    /// Complements the lazy expiry performed by `get`: long-lived idle keys are
+    /// swept out here even if they are never read again.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use rscache::{MemoryDatabase, Value};
+    ///
+    /// let mut db = MemoryDatabase::new();
+    /// db.set_with_ttl(String::from("tmp"), Value::Integer(1), Duration::from_millis(0));
+    /// db.expire_pass();
+    ///
+    /// assert_eq!(db.size(), 0);
+    /// ```
+    pub fn expire_pass(&mut self) {
+        let expired: Vec<String> = self
+            .items
+            .iter()
+            .filter(|(_, entry)| entry.is_expired())
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in expired {
+            self.unlink(&key);
+        }
     }
 
     /// Removes a key-value pair from the database
@@ -169,29 +372,161 @@ impl MemoryDatabase {
             return Err(DatabaseError::KeyDoesNotExist);
         }
 
-        let item_size = Self::calculate_value_size(key, self.items.get(key).unwrap());
-
-        self.items.remove(key);
-        self.size -= item_size;
+        self.unlink(key);
 
         Ok(())
     }
 
-    /// Calculates the value size based on the key and value
-    fn calculate_value_size(key: &str, value: &Value) -> i32 {
-        let value_size: i32;
+    /// Returns the remaining time-to-live for `key`
+    ///
+    /// `None` means the key is absent (honoring lazy expiry), `Some(None)`
+    /// means the key exists with no expiry, and `Some(Some(dur))` is the time
+    /// left before the deadline.
+    pub fn ttl(&mut self, key: &str) -> Option<Option<Duration>> {
+        self.get(key)?;
+        let entry = self.items.get(key)?;
+        Some(
+            entry
+                .expires_at
+                .map(|deadline| deadline.saturating_duration_since(Instant::now())),
+        )
+    }
 
-        match value {
-            Value::Integer(_) => value_size = 4,
-            Value::String(value) => value_size = value.len() as i32,
-            Value::Float(_) => value_size = 8,
-            Value::Boolean(_) => value_size = 1,
+    /// Sets a new expiry deadline on an existing key
+    ///
+    /// Returns `false` when the key is absent (or already expired); otherwise
+    /// the deadline is replaced and `true` is returned.
+    pub fn set_expiry(&mut self, key: &str, ttl: Duration) -> bool {
+        if self.get(key).is_none() {
+            return false;
+        }
+
+        match self.items.get_mut(key) {
+            Some(entry) => {
+                entry.expires_at = Some(Instant::now() + ttl);
+                true
+            }
+            None => false,
         }
+    }
+
+    /// Calculates the value size based on the key and value
+    fn calculate_value_size(key: &str, value: &Value) -> i32 {
+        let value_size = match value {
+            Value::Integer(_) => 4,
+            Value::String(value) => value.len() as i32,
+            Value::Float(_) => 8,
+            Value::Boolean(_) => 1,
+        };
 
         value_size + key.len() as i32
     }
 }
 
+impl Default for MemoryDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A thread-safe cache that partitions the keyspace across independent shards.
+///
+/// Each shard is a [`MemoryDatabase`] behind its own `Mutex`, and a key is
+/// routed to a shard by hashing. Unrelated keys therefore land on different
+/// locks and rarely contend. The `max_size` budget is split evenly across the
+/// shards so the total memory bound is honored despite the independent locks.
+///
+/// A `Mutex` rather than an `RwLock` guards each shard because even `get` needs
+/// `&mut` access to the underlying [`MemoryDatabase`] (for the LRU recency
+/// touch and lazy expiry), so reads could never share an `RwLock` anyway.
+pub struct ConcurrentDatabase {
+    shards: Vec<Mutex<MemoryDatabase>>,
+}
+
+impl ConcurrentDatabase {
+    /// Creates a concurrent database with a sensible default shard count
+    pub fn new() -> Self {
+        Self::with_shards(16, EvictionPolicy::RejectOnFull)
+    }
+
+    /// Creates a concurrent database split into `shards` partitions
+    ///
+    /// # Examples
+    /// ```
+    /// use rscache::{ConcurrentDatabase, EvictionPolicy, Value};
+    ///
+    /// let db = ConcurrentDatabase::with_shards(8, EvictionPolicy::Lru);
+    /// db.set(String::from("test"), Value::Integer(1)).unwrap();
+    /// ```
+    pub fn with_shards(shards: usize, policy: EvictionPolicy) -> Self {
+        let shards = shards.max(1);
+        // Divide the global budget so the sum of shard limits matches the
+        // single-database default; any remainder is spread over the first shards.
+        let total = 1024 * 1024;
+        let base = total / shards as i32;
+        let remainder = total % shards as i32;
+
+        let shards = (0..shards)
+            .map(|i| {
+                let mut db = MemoryDatabase::with_policy(policy);
+                db.max_size = base + if (i as i32) < remainder { 1 } else { 0 };
+                Mutex::new(db)
+            })
+            .collect();
+
+        Self { shards }
+    }
+
+    /// Routes a key to its shard by hashing
+    fn shard(&self, key: &str) -> &Mutex<MemoryDatabase> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        &self.shards[(hasher.finish() as usize) % self.shards.len()]
+    }
+
+    /// Sets a key-value pair on the shard that owns the key
+    pub fn set(&self, key: String, value: Value) -> Result<(), DatabaseError> {
+        self.shard(&key).lock().unwrap().set(key, value)
+    }
+
+    /// Sets a key-value pair that expires after `ttl` on the owning shard
+    pub fn set_with_ttl(
+        &self,
+        key: String,
+        value: Value,
+        ttl: Duration,
+    ) -> Result<(), DatabaseError> {
+        self.shard(&key).lock().unwrap().set_with_ttl(key, value, ttl)
+    }
+
+    /// Returns a clone of the value stored under `key`, if present
+    ///
+    /// A clone is returned rather than a borrow because the shard lock is
+    /// released as soon as this call completes.
+    pub fn get(&self, key: &str) -> Option<Value> {
+        self.shard(key).lock().unwrap().get(key).cloned()
+    }
+
+    /// Removes a key-value pair from the shard that owns the key
+    pub fn remove(&self, key: &str) -> Result<(), DatabaseError> {
+        self.shard(key).lock().unwrap().remove(key)
+    }
+
+    /// Returns the total size across all shards
+    pub fn size(&self) -> i32 {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().size())
+            .sum()
+    }
+}
+
+impl Default for ConcurrentDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -222,11 +557,11 @@ mod test {
     fn test_get_float_cache_item() {
         let mut db = MemoryDatabase::new();
 
-        db.set(String::from("pi"), Value::Float(3.14)).unwrap();
+        db.set(String::from("half"), Value::Float(2.5)).unwrap();
 
-        let value: f64 = *db.get("pi").unwrap().to().unwrap();
+        let value: f64 = *db.get("half").unwrap().to().unwrap();
 
-        assert_eq!(value, 3.14);
+        assert_eq!(value, 2.5);
     }
 
     #[test]
@@ -238,7 +573,92 @@ mod test {
 
         let value: bool = *db.get("is_active").unwrap().to().unwrap();
 
-        assert_eq!(value, true);
+        assert!(value);
+    }
+
+    #[test]
+    fn test_concurrent_database_threaded_access() {
+        use std::sync::Arc;
+        use std::thread;
+
+        let db = Arc::new(ConcurrentDatabase::new());
+        let mut handles = Vec::new();
+
+        for t in 0..8 {
+            let db = Arc::clone(&db);
+            handles.push(thread::spawn(move || {
+                for i in 0..100 {
+                    // Threads share the low key range (contended) and own a
+                    // disjoint high range, exercising both lock paths.
+                    let shared = format!("shared-{}", i % 10);
+                    let owned = format!("owned-{}-{}", t, i);
+                    db.set(shared.clone(), Value::Integer(i)).unwrap();
+                    db.set(owned.clone(), Value::Integer(i)).unwrap();
+                    db.get(&shared);
+                    db.remove(&owned).unwrap();
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // Every owned key was removed, so only the 10 shared keys remain.
+        for i in 0..10 {
+            assert!(db.get(&format!("shared-{}", i)).is_some());
+        }
+        assert_eq!(db.size(), 10 * ("shared-0".len() as i32 + 4));
+    }
+
+    #[test]
+    fn test_get_expired_key_is_absent_and_reaped() {
+        let mut db = MemoryDatabase::new();
+
+        db.set_with_ttl(
+            String::from("test"),
+            Value::String(String::from("test")),
+            Duration::from_millis(0),
+        )
+        .unwrap();
+
+        assert!(db.get("test").is_none());
+        assert_eq!(db.size(), 0);
+    }
+
+    #[test]
+    fn test_expire_pass_purges_idle_keys() {
+        let mut db = MemoryDatabase::new();
+
+        db.set_with_ttl(
+            String::from("tmp"),
+            Value::Integer(1),
+            Duration::from_millis(0),
+        )
+        .unwrap();
+        db.set(String::from("keep"), Value::Integer(2)).unwrap();
+
+        db.expire_pass();
+
+        assert!(db.get("tmp").is_none());
+        assert_eq!(db.size(), 8);
+    }
+
+    #[test]
+    fn test_reset_clears_ttl() {
+        let mut db = MemoryDatabase::new();
+
+        db.set_with_ttl(
+            String::from("test"),
+            Value::Integer(1),
+            Duration::from_millis(0),
+        )
+        .unwrap();
+        db.set(String::from("test"), Value::Integer(2)).unwrap();
+
+        let value: i32 = *db.get("test").unwrap().to().unwrap();
+
+        assert_eq!(value, 2);
     }
 
     #[test]
@@ -252,4 +672,43 @@ mod test {
 
         assert_eq!(result, DatabaseError::MaxSizeExceeded);
     }
+
+    #[test]
+    fn test_rejected_overwrite_keeps_existing_value() {
+        let mut db = MemoryDatabase::new();
+        // Room for "k" plus a one-byte value (2 bytes), but not a longer one.
+        db.max_size = 2;
+
+        db.set(String::from("k"), Value::String(String::from("a")))
+            .unwrap();
+
+        let result = db
+            .set(String::from("k"), Value::String(String::from("aaaa")))
+            .unwrap_err();
+
+        assert_eq!(result, DatabaseError::MaxSizeExceeded);
+        // The rejected overwrite must leave the original entry untouched.
+        let value: &String = db.get("k").unwrap().to().unwrap();
+        assert_eq!(value, "a");
+        assert_eq!(db.size(), 2);
+    }
+
+    #[test]
+    fn test_lru_evicts_least_recently_used() {
+        let mut db = MemoryDatabase::with_policy(EvictionPolicy::Lru);
+        // Each integer entry costs `key.len() + 4`, i.e. 5 bytes; cap at two.
+        db.max_size = 10;
+
+        db.set(String::from("a"), Value::Integer(1)).unwrap();
+        db.set(String::from("b"), Value::Integer(2)).unwrap();
+
+        // Touch "a" so "b" becomes the least-recently-used victim.
+        db.get("a");
+        db.set(String::from("c"), Value::Integer(3)).unwrap();
+
+        assert!(db.get("b").is_none());
+        assert!(db.get("a").is_some());
+        assert!(db.get("c").is_some());
+        assert_eq!(db.size(), 10);
+    }
 }
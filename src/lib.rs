@@ -0,0 +1,7 @@
+pub mod commands;
+pub mod database;
+pub mod resp;
+
+pub use database::{
+    ConcurrentDatabase, DatabaseError, EvictionPolicy, MemoryDatabase, Value, ValueConvert,
+};
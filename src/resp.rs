@@ -1,147 +1,288 @@
-#[derive(PartialEq, Eq)]
-enum Token {
-    String(String),
-    Array(usize),
+/// A decoded RESP value.
+///
+/// The enum is recursive so arrays can nest, and it models the reply side of
+/// the protocol (integers, errors, and the null bulk string/array) in addition
+/// to the request side.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Resp {
+    SimpleString(String),
+    Error(String),
+    Integer(i64),
+    /// A bulk string; `None` is the null bulk string (`$-1\r\n`).
+    BulkString(Option<Vec<u8>>),
+    /// An array; `None` is the null array (`*-1\r\n`).
+    Array(Option<Vec<Resp>>),
 }
 
-struct Lexer<'a> {
-    input: Box<dyn Iterator<Item = char> + 'a>,
+/// Errors produced while decoding a RESP frame.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// More bytes are needed before a full frame can be decoded.
+    ///
+    /// Already-consumed input is retained, so the caller can push further bytes
+    /// with [`Parser::feed`] and retry [`Parser::parse`].
+    Incomplete,
+    /// The bytes did not follow the protocol grammar.
+    Malformed(String),
 }
 
-impl<'a> Lexer<'a> {
-    pub fn new<I>(input: I) -> Self
-    where
-        I: IntoIterator<Item = char> + 'a,
-    {
+/// A byte-oriented, incremental RESP decoder.
+///
+/// The parser buffers raw bytes fed from a socket (or any source) and decodes
+/// one frame at a time. Bulk strings are read by their declared length so they
+/// may contain `\r`, `\n`, or any other byte, and a partial buffer yields
+/// [`ParseError::Incomplete`] without discarding the bytes seen so far.
+pub struct Parser {
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl Parser {
+    /// Creates a parser seeded with `input`'s bytes.
+    pub fn new<B: AsRef<[u8]>>(input: B) -> Self {
         Self {
-            input: Box::new(input.into_iter()),
+            buf: input.as_ref().to_vec(),
+            pos: 0,
         }
     }
 
-    pub fn next(&mut self) -> Option<Token> {
-        enum CurrToken {
-            SimpleString,
-            QuotedString,
-            BulkString,
-            Array,
+    /// Appends more bytes to the internal buffer.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Attempts to decode a single RESP frame from the buffered input.
+    ///
+    /// Returns [`ParseError::Incomplete`] when more bytes are needed; the
+    /// cursor is only advanced once a full frame has been decoded.
+    pub fn parse(&mut self) -> Result<Resp, ParseError> {
+        let mut cursor = Cursor {
+            input: &self.buf[self.pos..],
+            pos: 0,
+        };
+        let value = cursor.parse_value()?;
+
+        // Commit only on success so an incomplete frame can be retried as more
+        // bytes arrive. Drop fully-consumed prefixes to bound memory growth.
+        self.pos += cursor.pos;
+        if self.pos == self.buf.len() {
+            self.buf.clear();
+            self.pos = 0;
         }
 
-        loop {
-            let c = self.input.next()?;
+        Ok(value)
+    }
+}
+
+/// A non-consuming view over the pending bytes used while decoding one frame.
+struct Cursor<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
 
-            let curr_token = match c {
-                '*' => CurrToken::Array,
-                '$' => CurrToken::BulkString,
-                'a'..='z' | 'A'..='Z' => CurrToken::SimpleString,
-                '"' => CurrToken::QuotedString,
-                _ => continue,
-            };
+impl Cursor<'_> {
+    fn parse_value(&mut self) -> Result<Resp, ParseError> {
+        let marker = self.next_byte()?;
 
-            break match curr_token {
-                CurrToken::Array => {
-                    let size = self.read_size();
+        match marker {
+            b'+' => Ok(Resp::SimpleString(self.read_line_string()?)),
+            b'-' => Ok(Resp::Error(self.read_line_string()?)),
+            b':' => self.read_integer().map(Resp::Integer),
+            // RESP3 null (`_\r\n`).
+            b'_' => {
+                self.read_line()?;
+                Ok(Resp::BulkString(None))
+            }
+            b'$' => self.parse_bulk_string(),
+            b'*' => self.parse_array(),
+            other => Err(ParseError::Malformed(format!(
+                "unexpected type marker '{}'",
+                other as char
+            ))),
+        }
+    }
 
-                    Some(Token::Array(size))
-                }
-                CurrToken::BulkString => {
-                    let size = self.read_size();
-                    let mut s = String::new();
+    fn parse_bulk_string(&mut self) -> Result<Resp, ParseError> {
+        let len = self.read_integer()?;
 
-                    for _ in 0..size {
-                        let c = self.input.next()?;
-                        s.push(c);
-                    }
+        if len < 0 {
+            return Ok(Resp::BulkString(None));
+        }
 
-                    Some(Token::String(s))
-                }
-                CurrToken::SimpleString => {
-                    let mut s = String::new();
+        let len = len as usize;
+        if self.input.len() < self.pos + len + 2 {
+            return Err(ParseError::Incomplete);
+        }
 
-                    s.push(c);
+        let bytes = self.input[self.pos..self.pos + len].to_vec();
+        self.pos += len;
+        self.expect_crlf()?;
 
-                    loop {
-                        let c = self.input.next()?;
+        Ok(Resp::BulkString(Some(bytes)))
+    }
 
-                        if c.is_whitespace() {
-                            break;
-                        }
+    fn parse_array(&mut self) -> Result<Resp, ParseError> {
+        let size = self.read_integer()?;
 
-                        s.push(c);
-                    }
+        if size < 0 {
+            return Ok(Resp::Array(None));
+        }
 
-                    Some(Token::String(s))
-                }
-                CurrToken::QuotedString => {
-                    let mut s = String::new();
+        // Read exactly the declared number of children.
+        let mut items = Vec::with_capacity(size as usize);
+        for _ in 0..size {
+            items.push(self.parse_value()?);
+        }
 
-                    loop {
-                        let c = self.input.next()?;
+        Ok(Resp::Array(Some(items)))
+    }
 
-                        if c == '"' {
-                            break;
-                        }
+    fn next_byte(&mut self) -> Result<u8, ParseError> {
+        let byte = *self.input.get(self.pos).ok_or(ParseError::Incomplete)?;
+        self.pos += 1;
+        Ok(byte)
+    }
 
-                        s.push(c);
-                    }
+    /// Returns the bytes up to the next `\r\n`, advancing past the terminator.
+    fn read_line(&mut self) -> Result<&[u8], ParseError> {
+        let rest = &self.input[self.pos..];
+        let cr = rest
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .ok_or(ParseError::Incomplete)?;
 
-                    Some(Token::String(s))
-                }
-            };
-        }
+        let start = self.pos;
+        self.pos += cr + 2;
+        Ok(&self.input[start..start + cr])
     }
 
-    fn read_size(&mut self) -> usize {
-        let mut size = String::new();
+    fn read_line_string(&mut self) -> Result<String, ParseError> {
+        let line = self.read_line()?;
+        String::from_utf8(line.to_vec())
+            .map_err(|_| ParseError::Malformed(String::from("invalid UTF-8 in line")))
+    }
 
-        while let Some(c) = self.input.next() {
-            if c.is_digit(10) {
-                size.push(c);
-            } else if c == '\r' {
-                let mut iter = self.input.as_mut().peekable();
+    fn read_integer(&mut self) -> Result<i64, ParseError> {
+        let line = self.read_line_string()?;
+        line.parse()
+            .map_err(|_| ParseError::Malformed(format!("invalid integer '{}'", line)))
+    }
 
-                // TODO: Not sure why but this peek is moving the iterator cursor.
-                // Need to find a way to improve this
-                if let Some(n) = iter.peek() {
-                    if *n == '\n' {
-                        break;
-                    }
-                }
+    fn expect_crlf(&mut self) -> Result<(), ParseError> {
+        match (self.input.get(self.pos), self.input.get(self.pos + 1)) {
+            (Some(b'\r'), Some(b'\n')) => {
+                self.pos += 2;
+                Ok(())
             }
+            (Some(_), Some(_)) => Err(ParseError::Malformed(String::from("expected CRLF"))),
+            _ => Err(ParseError::Incomplete),
         }
-
-        size.parse().unwrap()
     }
 }
 
-pub struct Parser<'a> {
-    lexer: Lexer<'a>,
+/// Serializes a [`Resp`] value into its wire-format bytes.
+pub fn encode(value: &Resp) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode_into(value, &mut out);
+    out
 }
 
-impl<'a> Parser<'a> {
-    pub fn new(input: &'a str) -> Self {
-        Self {
-            lexer: Lexer::new(input.chars()),
+fn encode_into(value: &Resp, out: &mut Vec<u8>) {
+    match value {
+        Resp::SimpleString(s) => {
+            out.push(b'+');
+            out.extend_from_slice(s.as_bytes());
+            out.extend_from_slice(b"\r\n");
+        }
+        Resp::Error(s) => {
+            out.push(b'-');
+            out.extend_from_slice(s.as_bytes());
+            out.extend_from_slice(b"\r\n");
+        }
+        Resp::Integer(n) => {
+            out.extend_from_slice(format!(":{}\r\n", n).as_bytes());
+        }
+        Resp::BulkString(None) => out.extend_from_slice(b"$-1\r\n"),
+        Resp::BulkString(Some(bytes)) => {
+            out.extend_from_slice(format!("${}\r\n", bytes.len()).as_bytes());
+            out.extend_from_slice(bytes);
+            out.extend_from_slice(b"\r\n");
+        }
+        Resp::Array(None) => out.extend_from_slice(b"*-1\r\n"),
+        Resp::Array(Some(items)) => {
+            out.extend_from_slice(format!("*{}\r\n", items.len()).as_bytes());
+            for item in items {
+                encode_into(item, out);
+            }
         }
     }
+}
 
-    pub fn parse(&mut self) -> Vec<String> {
-        let mut tokens = Vec::new();
+#[cfg(test)]
+mod test {
+    use super::*;
 
-        while let Some(token) = self.lexer.next() {
-            match token {
-                Token::String(s) => tokens.push(s),
-                Token::Array(size) => {
-                    let inner_tokens = self.parse();
+    #[test]
+    fn test_parse_array_of_bulk_strings() {
+        let mut parser = Parser::new("*2\r\n$3\r\nGET\r\n$3\r\nkey\r\n");
 
-                    if inner_tokens.iter().count() != size {
-                        panic!("Array size mismatch");
-                    }
+        let frame = parser.parse().unwrap();
 
-                    inner_tokens.iter().for_each(|t| tokens.push(t.to_string()));
-                }
-            }
-        }
+        assert_eq!(
+            frame,
+            Resp::Array(Some(vec![
+                Resp::BulkString(Some(b"GET".to_vec())),
+                Resp::BulkString(Some(b"key".to_vec())),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_parse_integer_and_null() {
+        let mut parser = Parser::new(":42\r\n");
+        assert_eq!(parser.parse().unwrap(), Resp::Integer(42));
+
+        let mut parser = Parser::new("$-1\r\n");
+        assert_eq!(parser.parse().unwrap(), Resp::BulkString(None));
+    }
+
+    #[test]
+    fn test_bulk_string_is_binary_safe() {
+        // A payload containing an embedded CRLF must survive intact.
+        let mut parser = Parser::new(&b"$6\r\na\r\nb\r\n\r\n"[..]);
+
+        assert_eq!(
+            parser.parse().unwrap(),
+            Resp::BulkString(Some(b"a\r\nb\r\n".to_vec()))
+        );
+    }
+
+    #[test]
+    fn test_incremental_feed() {
+        let mut parser = Parser::new(&b"*1\r\n$3\r\nGE"[..]);
+        assert_eq!(parser.parse(), Err(ParseError::Incomplete));
+
+        // Feeding the remainder completes the frame without losing the prefix.
+        parser.feed(b"T\r\n");
+        assert_eq!(
+            parser.parse().unwrap(),
+            Resp::Array(Some(vec![Resp::BulkString(Some(b"GET".to_vec()))]))
+        );
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let frame = Resp::Array(Some(vec![
+            Resp::BulkString(Some(b"SET".to_vec())),
+            Resp::BulkString(Some(b"k".to_vec())),
+            Resp::Integer(7),
+        ]));
+
+        let encoded = encode(&frame);
+        let decoded = Parser::new(std::str::from_utf8(&encoded).unwrap())
+            .parse()
+            .unwrap();
 
-        tokens
+        assert_eq!(decoded, frame);
     }
 }